@@ -13,7 +13,7 @@
 //! ```
 
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use regex_automata::{dense, DFA};
 use std::collections::{BTreeSet, HashMap};
 use std::ops::RangeInclusive;
@@ -37,8 +37,105 @@ fn range_to_tokens(range: RangeInclusive<u8>) -> TokenStream {
     }
 }
 
+/// Coalesce a set of bytes (or byte classes) into the smallest set of inclusive ranges that
+/// cover it.
+fn coalesce(bytes: &BTreeSet<u8>) -> Vec<RangeInclusive<u8>> {
+    let mut ranges = vec![];
+    let mut range: Option<RangeInclusive<u8>> = None;
+
+    for &byte in bytes {
+        if let Some(range) = &mut range {
+            if *range.end() == byte - 1 {
+                *range = *range.start()..=byte;
+                continue;
+            } else {
+                ranges.push(range.clone());
+            }
+        }
+        range = Some(byte..=byte);
+    }
+
+    if let Some(range) = range {
+        ranges.push(range);
+    }
+
+    ranges
+}
+
+/// Like [`coalesce`], but as token streams suitable for use as `match` patterns.
+fn coalesce_ranges(bytes: &BTreeSet<u8>) -> Vec<TokenStream> {
+    coalesce(bytes).into_iter().map(range_to_tokens).collect()
+}
+
+/// A partition of the 256 byte values into equivalence classes: every byte in a class transitions
+/// identically from every state a DFA can reach, so the generated code can key its `match` on the
+/// (usually much smaller) class id instead of the raw byte.
+struct ByteClasses {
+    class_of: [u8; 256],
+    /// One example byte per class, indexed by class id; representative bytes stand in for their
+    /// whole class when probing `RegexDfa::next_state`.
+    representatives: Vec<u8>,
+}
+
+impl ByteClasses {
+    fn compute(regex: &RegexDfa, state_ids: &BTreeSet<usize>) -> Self {
+        let mut class_of = [0; 256];
+        let mut representatives = vec![];
+        let mut signature_to_class: HashMap<Vec<usize>, u8> = HashMap::new();
+
+        for byte in 0..=255u8 {
+            let signature: Vec<usize> = state_ids
+                .iter()
+                .map(|&id| regex.next_state(id, byte))
+                .collect();
+
+            let class = *signature_to_class.entry(signature).or_insert_with(|| {
+                representatives.push(byte);
+                (representatives.len() - 1) as u8
+            });
+
+            class_of[byte as usize] = class;
+        }
+
+        Self {
+            class_of,
+            representatives,
+        }
+    }
+
+    fn table(&self, ident: &Ident) -> TokenStream {
+        let entries = self.class_of.iter();
+        quote!(const #ident: [u8; 256] = [#(#entries),*];)
+    }
+}
+
+/// All state ids a DFA can reach from `start`, found by following every byte's transition.
+fn reachable_states(regex: &RegexDfa, start: usize) -> BTreeSet<usize> {
+    let mut seen = BTreeSet::new();
+    let mut stack = vec![start];
+
+    while let Some(id) = stack.pop() {
+        if !seen.insert(id) {
+            continue;
+        }
+
+        if regex.is_dead_state(id) {
+            continue;
+        }
+
+        for byte in 0..=255u8 {
+            let next = regex.next_state(id, byte);
+            if !seen.contains(&next) {
+                stack.push(next);
+            }
+        }
+    }
+
+    seen
+}
+
 impl State {
-    fn from_regex(regex: &RegexDfa, state: usize) -> Self {
+    fn from_regex(regex: &RegexDfa, state: usize, representatives: &[u8]) -> Self {
         if regex.is_match_state(state) {
             Self::Match
         } else if regex.is_dead_state(state) {
@@ -46,12 +143,12 @@ impl State {
         } else {
             let mut transitions = HashMap::new();
 
-            for byte in 0..=255 {
+            for (class, &byte) in representatives.iter().enumerate() {
                 let next = regex.next_state(state, byte);
                 transitions
                     .entry(next)
                     .or_insert_with(BTreeSet::new)
-                    .insert(byte);
+                    .insert(class as u8);
             }
 
             Self::Transitions(transitions)
@@ -64,23 +161,7 @@ impl State {
             Self::Dead => parse_quote!(return false),
             Self::Transitions(transitions) => {
                 let branches = transitions.iter().map(|(target, bytes)| {
-                    let mut ranges = vec![];
-                    let mut range: Option<RangeInclusive<u8>> = None;
-                    for &byte in bytes {
-                        if let Some(range) = &mut range {
-                            if *range.end() == byte - 1 {
-                                *range = *range.start()..=byte;
-                                continue;
-                            } else {
-                                ranges.push(range_to_tokens(range.clone()));
-                            }
-                        }
-                        range = Some(byte..=byte);
-                    }
-
-                    if let Some(range) = range {
-                        ranges.push(range_to_tokens(range));
-                    }
+                    let ranges = coalesce_ranges(bytes);
 
                     let handler = match states[target] {
                         Self::Match => quote!(return true),
@@ -93,7 +174,110 @@ impl State {
 
                 parse_quote! {
                     match #byte {
-                        #(#branches),*
+                        #(#branches,)*
+                        #[allow(unreachable_patterns)]
+                        _ => unreachable!(),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Like [`Self::handle`], but for use inside [`match_regex_set`] where a pattern matching or
+    /// dying doesn't end the whole function: it just latches `matched`/`active` for that one
+    /// pattern and lets the shared scan loop keep running the others.
+    fn handle_step(
+        &self,
+        byte: &Ident,
+        states: &HashMap<usize, State>,
+        state: &Ident,
+        matched: &Ident,
+        active: &Ident,
+    ) -> Expr {
+        match self {
+            Self::Match => parse_quote!({ #matched = true; #active = false; #state }),
+            Self::Dead => parse_quote!({ #active = false; #state }),
+            Self::Transitions(transitions) => {
+                let branches = transitions.iter().map(|(target, bytes)| {
+                    let ranges = coalesce_ranges(bytes);
+
+                    let handler = match states[target] {
+                        Self::Match => quote!({ #matched = true; #active = false; #state }),
+                        Self::Dead => quote!({ #active = false; #state }),
+                        _ => quote!(#target),
+                    };
+
+                    quote!(#(#ranges)|* => #handler)
+                });
+
+                parse_quote! {
+                    match #byte {
+                        #(#branches,)*
+                        #[allow(unreachable_patterns)]
+                        _ => unreachable!(),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Like [`State`], but a match state still carries its outgoing transitions: finding the end of
+/// a match (in priority order, the same as `regex::Regex::find`) means continuing to scan past
+/// the first state reached that matches, in case a later byte extends it.
+#[derive(Clone, PartialEq)]
+enum FindState {
+    Dead,
+    Live {
+        is_match: bool,
+        transitions: HashMap<usize, BTreeSet<u8>>,
+    },
+}
+
+impl FindState {
+    fn from_regex(regex: &RegexDfa, state: usize, representatives: &[u8]) -> Self {
+        if regex.is_dead_state(state) {
+            Self::Dead
+        } else {
+            let is_match = regex.is_match_state(state);
+            let mut transitions = HashMap::new();
+
+            for (class, &byte) in representatives.iter().enumerate() {
+                let next = regex.next_state(state, byte);
+                transitions
+                    .entry(next)
+                    .or_insert_with(BTreeSet::new)
+                    .insert(class as u8);
+            }
+
+            Self::Live {
+                is_match,
+                transitions,
+            }
+        }
+    }
+
+    fn handle(&self, byte: &Ident, states: &HashMap<usize, FindState>, on_match: &Expr) -> Expr {
+        match self {
+            Self::Dead => parse_quote!(break),
+            Self::Live { transitions, .. } => {
+                let branches = transitions.iter().map(|(target, bytes)| {
+                    let ranges = coalesce_ranges(bytes);
+
+                    let handler = match &states[target] {
+                        Self::Dead => quote!(break),
+                        Self::Live { is_match: true, .. } => quote!({ #on_match; #target }),
+                        Self::Live { is_match: false, .. } => quote!(#target),
+                    };
+
+                    quote!(#(#ranges)|* => #handler)
+                });
+
+                parse_quote! {
+                    match #byte {
+                        #(#branches,)*
+                        #[allow(unreachable_patterns)]
+                        _ => unreachable!(),
                     }
                 }
             }
@@ -104,18 +288,19 @@ impl State {
 struct Dfa {
     start: usize,
     states: HashMap<usize, State>,
+    classes: ByteClasses,
 }
 
 impl Dfa {
-    fn add_states(&mut self, regex: &RegexDfa, id: usize) {
-        let state = State::from_regex(regex, id);
+    fn add_states(&mut self, regex: &RegexDfa, id: usize, representatives: &[u8]) {
+        let state = State::from_regex(regex, id, representatives);
 
         self.states.insert(id, state.clone());
 
         if let State::Transitions(transitions) = &state {
             for target in transitions.keys() {
                 if !self.states.contains_key(target) {
-                    self.add_states(regex, *target);
+                    self.add_states(regex, *target, representatives);
                 }
             }
         }
@@ -123,31 +308,41 @@ impl Dfa {
 
     fn from_regex(regex: &RegexDfa) -> Self {
         let start = regex.start_state();
+        let classes = ByteClasses::compute(regex, &reachable_states(regex, start));
+
         let mut dfa = Self {
             start,
             states: HashMap::new(),
+            classes,
         };
 
-        dfa.add_states(regex, start);
+        let representatives = dfa.classes.representatives.clone();
+        dfa.add_states(regex, start, &representatives);
 
         dfa
     }
 
     fn handle(&self, input: &Ident) -> Expr {
-        let byte = parse_quote!(byte);
+        let byte: Ident = parse_quote!(byte);
+        let class: Ident = parse_quote!(class);
+        let table_ident: Ident = parse_quote!(BYTE_CLASS);
+        let table = self.classes.table(&table_ident);
         let start = self.start;
 
         let branches = self.states.iter().map(|(id, state)| {
-            let body = state.handle(&byte, &self.states);
+            let body = state.handle(&class, &self.states);
             quote!(#id => #body)
         });
 
         parse_quote! {{
+            #table
+
             let mut i = 0;
             let mut state = #start;
 
             while i < #input.len() {
                 let #byte = #input[i];
+                let #class = #table_ident[#byte as usize];
 
                 state = match state {
                     #(#branches,)*
@@ -161,9 +356,296 @@ impl Dfa {
             return false;
         }}
     }
+
+    /// Like [`Self::handle`], but instead of returning from the whole function, evaluates to the
+    /// next state for the caller to assign to `state` (latching `matched`/`active` along the way)
+    /// so several of these can be driven by one shared scan loop in [`match_regex_set`].
+    fn handle_step(&self, byte: &Ident, state: &Ident, matched: &Ident, active: &Ident) -> Expr {
+        let branches = self.states.iter().map(|(id, s)| {
+            let body = s.handle_step(byte, &self.states, state, matched, active);
+            quote!(#id => #body)
+        });
+
+        parse_quote! {
+            match #state {
+                #(#branches,)*
+                #[allow(unconditional_panic)]
+                _ => [][0],
+            }
+        }
+    }
+}
+
+/// Like [`Dfa`], but built from [`FindState`] so match states keep their transitions around for
+/// [`find_regex`]'s priority-order scan.
+struct FindDfa {
+    start: usize,
+    states: HashMap<usize, FindState>,
+    classes: ByteClasses,
+}
+
+impl FindDfa {
+    fn add_states(&mut self, regex: &RegexDfa, id: usize, representatives: &[u8]) {
+        let state = FindState::from_regex(regex, id, representatives);
+
+        self.states.insert(id, state.clone());
+
+        if let FindState::Live { transitions, .. } = &state {
+            for target in transitions.keys() {
+                if !self.states.contains_key(target) {
+                    self.add_states(regex, *target, representatives);
+                }
+            }
+        }
+    }
+
+    fn from_regex(regex: &RegexDfa) -> Self {
+        let start = regex.start_state();
+        let classes = ByteClasses::compute(regex, &reachable_states(regex, start));
+
+        let mut dfa = Self {
+            start,
+            states: HashMap::new(),
+            classes,
+        };
+
+        let representatives = dfa.classes.representatives.clone();
+        dfa.add_states(regex, start, &representatives);
+
+        dfa
+    }
+
+    /// Scans `input` forward from `begin`, returning the end offset (exclusive) of the first
+    /// (in priority order) match starting at or after `begin`, if any.
+    fn handle_forward(&self, input: &Ident, begin: &Expr) -> Expr {
+        let byte: Ident = parse_quote!(byte);
+        let class: Ident = parse_quote!(class);
+        let table_ident: Ident = parse_quote!(BYTE_CLASS);
+        let table = self.classes.table(&table_ident);
+        let i: Ident = parse_quote!(i);
+        let start = self.start;
+        let on_match: Expr = parse_quote!(end = Some(#i + 1));
+
+        let branches = self.states.iter().map(|(id, state)| {
+            let body = state.handle(&class, &self.states, &on_match);
+            quote!(#id => #body)
+        });
+
+        let starts_matched = matches!(self.states[&start], FindState::Live { is_match: true, .. });
+        let initial_end: Expr = if starts_matched {
+            parse_quote!(Some(#begin))
+        } else {
+            parse_quote!(None)
+        };
+
+        parse_quote! {{
+            #table
+
+            let mut #i = #begin;
+            let mut state = #start;
+            let mut end: Option<usize> = #initial_end;
+
+            while #i < #input.len() {
+                let #byte = #input[#i];
+                let #class = #table_ident[#byte as usize];
+
+                state = match state {
+                    #(#branches,)*
+                    #[allow(unconditional_panic)]
+                    _ => [][0],
+                };
+
+                #i += 1;
+            }
+
+            end
+        }}
+    }
+
+    /// Scans `input` backward from `end`, returning the earliest offset the anchored reverse
+    /// automaton will still accept, i.e. the start of the match that ends at `end`.
+    fn handle_reverse(&self, input: &Ident, end: &Ident) -> Expr {
+        let byte: Ident = parse_quote!(byte);
+        let class: Ident = parse_quote!(class);
+        let table_ident: Ident = parse_quote!(BYTE_CLASS);
+        let table = self.classes.table(&table_ident);
+        let j: Ident = parse_quote!(j);
+        let start = self.start;
+        let on_match: Expr = parse_quote!(start = #j);
+
+        let branches = self.states.iter().map(|(id, state)| {
+            let body = state.handle(&class, &self.states, &on_match);
+            quote!(#id => #body)
+        });
+
+        parse_quote! {{
+            #table
+
+            let mut #j = #end;
+            let mut state = #start;
+            let mut start = #end;
+
+            while #j > 0 {
+                #j -= 1;
+                let #byte = #input[#j];
+                let #class = #table_ident[#byte as usize];
+
+                state = match state {
+                    #(#branches,)*
+                    #[allow(unconditional_panic)]
+                    _ => [][0],
+                };
+            }
+
+            start
+        }}
+    }
+}
+
+/// Sentinel target state ids used in a [`SparseDfa`]'s transition table in place of a dense state
+/// index, standing in for [`State::Match`] and [`State::Dead`] respectively. Safe as long as no
+/// real automaton has anywhere near `u32::MAX` live states.
+const SPARSE_MATCH: u32 = u32::MAX;
+const SPARSE_DEAD: u32 = u32::MAX - 1;
+
+/// Table-driven encoding of a [`Dfa`]: each live state's transitions become a contiguous run of
+/// `(range_end, target)` pairs in `transitions`, located via a per-state `(offset, len)` in
+/// `state_offsets`. Walking it is array indexing and a linear scan instead of a nested `match`,
+/// which trades a per-byte scan for much less generated code.
+struct SparseDfa {
+    start: u32,
+    transitions: Vec<(u8, u32)>,
+    state_offsets: Vec<(u32, u32)>,
+    classes: ByteClasses,
+}
+
+impl SparseDfa {
+    fn from_dfa(dfa: Dfa) -> Self {
+        let mut ids: Vec<usize> = dfa
+            .states
+            .iter()
+            .filter(|(_, state)| matches!(state, State::Transitions(_)))
+            .map(|(&id, _)| id)
+            .collect();
+        ids.sort_unstable();
+
+        let dense_id: HashMap<usize, u32> = ids
+            .iter()
+            .enumerate()
+            .map(|(index, &id)| (id, index as u32))
+            .collect();
+
+        let states = &dfa.states;
+        let target_id = |id: usize| -> u32 {
+            match &states[&id] {
+                State::Match => SPARSE_MATCH,
+                State::Dead => SPARSE_DEAD,
+                State::Transitions(_) => dense_id[&id],
+            }
+        };
+
+        let mut transitions = vec![];
+        let mut state_offsets = vec![];
+
+        for &id in &ids {
+            let offset = transitions.len() as u32;
+
+            let map = match &states[&id] {
+                State::Transitions(map) => map,
+                _ => unreachable!(),
+            };
+
+            let mut entries: Vec<(u8, u8, u32)> = map
+                .iter()
+                .flat_map(|(&target, classes)| {
+                    let target = target_id(target);
+                    coalesce(classes)
+                        .into_iter()
+                        .map(move |range| (*range.start(), *range.end(), target))
+                })
+                .collect();
+            entries.sort_unstable_by_key(|&(range_start, _, _)| range_start);
+
+            for &(_, range_end, target) in &entries {
+                transitions.push((range_end, target));
+            }
+
+            state_offsets.push((offset, entries.len() as u32));
+        }
+
+        let start = target_id(dfa.start);
+
+        Self {
+            start,
+            transitions,
+            state_offsets,
+            classes: dfa.classes,
+        }
+    }
+
+    fn handle(&self, input: &Ident) -> Expr {
+        let byte: Ident = parse_quote!(byte);
+        let class: Ident = parse_quote!(class);
+        let table_ident: Ident = parse_quote!(BYTE_CLASS);
+        let class_table = self.classes.table(&table_ident);
+
+        let start = self.start;
+        let match_state = SPARSE_MATCH;
+        let dead_state = SPARSE_DEAD;
+
+        let transition_entries = self
+            .transitions
+            .iter()
+            .map(|(range_end, target)| quote!((#range_end, #target)));
+        let offset_entries = self
+            .state_offsets
+            .iter()
+            .map(|(offset, len)| quote!((#offset, #len)));
+
+        parse_quote! {{
+            #class_table
+
+            const TRANSITIONS: &[(u8, u32)] = &[#(#transition_entries),*];
+            const STATE_OFFSETS: &[(u32, u32)] = &[#(#offset_entries),*];
+
+            let mut i = 0;
+            let mut state: u32 = #start;
+
+            while i < #input.len() {
+                if state == #match_state {
+                    return true;
+                }
+
+                if state == #dead_state {
+                    return false;
+                }
+
+                let #byte = #input[i];
+                let #class = #table_ident[#byte as usize];
+
+                let (offset, len) = STATE_OFFSETS[state as usize];
+                let mut j = 0;
+                let mut next = #dead_state;
+
+                while j < len {
+                    let (range_end, target) = TRANSITIONS[(offset + j) as usize];
+                    if #class <= range_end {
+                        next = target;
+                        break;
+                    }
+                    j += 1;
+                }
+
+                state = next;
+                i += 1;
+            }
+
+            state == #match_state
+        }}
+    }
 }
 
-fn build_dfa(regex: &str) -> RegexDfa {
+fn build_dfa(regex: &str, reverse: bool) -> RegexDfa {
     let (regex, anchored) = if let Some(regex) = regex.strip_prefix('^') {
         (regex, true)
     } else {
@@ -174,7 +656,8 @@ fn build_dfa(regex: &str) -> RegexDfa {
         .byte_classes(false)
         .premultiply(false)
         .minimize(true)
-        .anchored(anchored)
+        .anchored(anchored || reverse)
+        .reverse(reverse)
         .build(regex)
         .unwrap();
 
@@ -203,14 +686,73 @@ impl Parse for Args {
     }
 }
 
-/// See crate documentation.
+struct MatchArgs {
+    sparse: bool,
+    regex: String,
+    expr: Expr,
+}
+
+impl Parse for MatchArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let sparse = if input.peek(Ident) {
+            let ident: Ident = input.parse()?;
+            if ident != "sparse" {
+                return Err(Error::new_spanned(ident, "expected `sparse` or a string literal"));
+            }
+            true
+        } else {
+            false
+        };
+
+        let regex_lit: LitStr = input.parse()?;
+        let _comma_token: Token![,] = input.parse()?;
+        let expr = input.parse()?;
+
+        Ok(Self {
+            sparse,
+            regex: regex_lit.value(),
+            expr,
+        })
+    }
+}
+
+struct SetArgs {
+    regexes: Vec<String>,
+    expr: Expr,
+}
+
+impl Parse for SetArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        bracketed!(content in input);
+        let regex_lits = content.parse_terminated::<LitStr, Token![,]>(Parse::parse)?;
+        let _comma_token: Token![,] = input.parse()?;
+        let expr = input.parse()?;
+
+        Ok(Self {
+            regexes: regex_lits.iter().map(LitStr::value).collect(),
+            expr,
+        })
+    }
+}
+
+/// See crate documentation. A leading `sparse` token (`match_regex!(sparse "pat", bytes)`)
+/// switches to table-driven codegen: flat transition tables instead of a nested `match`, at the
+/// cost of a scan over the current state's table entries per byte. Useful when an automaton is
+/// large enough that the default codegen is slow to compile or bloats the binary.
 #[proc_macro]
 pub fn match_regex(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let args = parse_macro_input!(input as Args);
-    let regex = build_dfa(&args.regex);
+    let args = parse_macro_input!(input as MatchArgs);
+    let regex = build_dfa(&args.regex, false);
     let dfa = Dfa::from_regex(&regex);
     let input_token = parse_quote!(input);
-    let block = dfa.handle(&input_token);
+
+    let block = if args.sparse {
+        SparseDfa::from_dfa(dfa).handle(&input_token)
+    } else {
+        dfa.handle(&input_token)
+    };
+
     let input_expr = args.expr;
 
     let tokens = quote! {{
@@ -223,3 +765,210 @@ pub fn match_regex(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 
     tokens.into()
 }
+
+/// Match a byte slice against a fixed set of patterns in a single scan, yielding which of them
+/// matched.
+///
+/// ```
+/// const fn classify(bytes: &[u8]) -> [bool; 2] {
+///     const_regex::match_regex_set!(["^GET ", "^POST "], bytes)
+/// }
+///
+/// assert_eq!(classify(b"GET /"), [true, false]);
+/// assert_eq!(classify(b"POST /"), [false, true]);
+/// assert_eq!(classify(b"PUT /"), [false, false]);
+/// ```
+#[proc_macro]
+pub fn match_regex_set(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let args = parse_macro_input!(input as SetArgs);
+    let input_token: Ident = parse_quote!(input);
+    let byte: Ident = parse_quote!(byte);
+    let count = args.regexes.len();
+
+    let mut inits = vec![];
+    let mut steps = vec![];
+    let mut results = vec![];
+
+    for regex_str in &args.regexes {
+        let regex = build_dfa(regex_str, false);
+        let dfa = Dfa::from_regex(&regex);
+        let start = dfa.start;
+        let starts_matched = matches!(dfa.states[&start], State::Match);
+
+        let index = results.len();
+        let state_ident = format_ident!("state_{}", index);
+        let matched_ident = format_ident!("matched_{}", index);
+        let active_ident = format_ident!("active_{}", index);
+        let class_ident = format_ident!("class_{}", index);
+        let table_ident = format_ident!("BYTE_CLASS_{}", index);
+
+        let table = dfa.classes.table(&table_ident);
+        let step = dfa.handle_step(&class_ident, &state_ident, &matched_ident, &active_ident);
+
+        inits.push(quote! {
+            #table
+            let mut #state_ident = #start;
+            let mut #matched_ident = #starts_matched;
+            let mut #active_ident = true;
+        });
+
+        steps.push(quote! {
+            if #active_ident {
+                let #class_ident = #table_ident[#byte as usize];
+                #state_ident = #step;
+            }
+        });
+
+        results.push(quote!(#matched_ident));
+    }
+
+    let input_expr = args.expr;
+
+    let tokens = quote! {{
+        const fn match_regex_set(#input_token: &[u8]) -> [bool; #count] {
+            #(#inits)*
+
+            let mut i = 0;
+
+            while i < #input_token.len() {
+                let #byte = #input_token[i];
+
+                #(#steps)*
+
+                i += 1;
+            }
+
+            [#(#results),*]
+        }
+
+        match_regex_set(#input_expr)
+    }};
+
+    tokens.into()
+}
+
+/// Find a match of a pattern in a byte slice (in the same priority order as `regex::Regex::find`,
+/// not POSIX leftmost-longest), returning the start and end byte offsets of the match.
+///
+/// ```
+/// const fn find(bytes: &[u8]) -> Option<(usize, usize)> {
+///     const_regex::find_regex!("b+", bytes)
+/// }
+///
+/// assert_eq!(find(b"abbbc"), Some((1, 4)));
+/// assert_eq!(find(b"xyz"), None);
+/// ```
+#[proc_macro]
+pub fn find_regex(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let args = parse_macro_input!(input as Args);
+    let anchored = args.regex.starts_with('^');
+
+    let forward_regex = build_dfa(&args.regex, false);
+    let forward_dfa = FindDfa::from_regex(&forward_regex);
+    let input_token: Ident = parse_quote!(input);
+    let end_ident: Ident = parse_quote!(end);
+
+    let forward_block = forward_dfa.handle_forward(&input_token, &parse_quote!(0));
+
+    let start_expr: Expr = if anchored {
+        parse_quote!(0)
+    } else {
+        let reverse_regex = build_dfa(&args.regex, true);
+        let reverse_dfa = FindDfa::from_regex(&reverse_regex);
+        reverse_dfa.handle_reverse(&input_token, &end_ident)
+    };
+
+    let input_expr = args.expr;
+
+    let tokens = quote! {{
+        const fn find_regex(#input_token: &[u8]) -> Option<(usize, usize)> {
+            let end = #forward_block;
+
+            match end {
+                Some(#end_ident) => Some((#start_expr, #end_ident)),
+                None => None,
+            }
+        }
+
+        find_regex(#input_expr)
+    }};
+
+    tokens.into()
+}
+
+/// Count the number of non-overlapping matches of a pattern in a byte slice (the same semantics
+/// as `Regex::find_iter`, including its priority-order match selection).
+///
+/// ```
+/// const fn count(bytes: &[u8]) -> usize {
+///     const_regex::count_regex!("b+", bytes)
+/// }
+///
+/// assert_eq!(count(b"abbbcbd"), 2);
+/// assert_eq!(count(b"xyz"), 0);
+/// ```
+#[proc_macro]
+pub fn count_regex(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let args = parse_macro_input!(input as Args);
+    let anchored = args.regex.starts_with('^');
+
+    let regex = build_dfa(&args.regex, false);
+    let dfa = FindDfa::from_regex(&regex);
+    let input_token: Ident = parse_quote!(input);
+    let cursor: Ident = parse_quote!(cursor);
+
+    let find_block = dfa.handle_forward(&input_token, &parse_quote!(#cursor));
+    let input_expr = args.expr;
+
+    let body: Expr = if anchored {
+        parse_quote! {{
+            let #cursor = 0;
+
+            match #find_block {
+                Some(_) => 1,
+                None => 0,
+            }
+        }}
+    } else {
+        parse_quote! {{
+            let mut #cursor = 0;
+            let mut count = 0;
+            let mut last_match: Option<usize> = None;
+
+            while #cursor <= #input_token.len() {
+                match #find_block {
+                    Some(end) => {
+                        // An empty match immediately after the match that ended the previous
+                        // iteration doesn't count as a new, distinct match (the same rule
+                        // `Regex::find_iter` applies), or runs like "a*" on "aabaa" would
+                        // double-count the boundary between "aa" and the next "aa".
+                        let skip = match last_match {
+                            Some(prev_end) => end == #cursor && prev_end == #cursor,
+                            None => false,
+                        };
+
+                        #cursor = if end == #cursor { end + 1 } else { end };
+
+                        if !skip {
+                            count += 1;
+                            last_match = Some(end);
+                        }
+                    }
+                    None => break,
+                }
+            }
+
+            count
+        }}
+    };
+
+    let tokens = quote! {{
+        const fn count_regex(#input_token: &[u8]) -> usize {
+            #body
+        }
+
+        count_regex(#input_expr)
+    }};
+
+    tokens.into()
+}
@@ -0,0 +1,40 @@
+use const_regex::count_regex;
+
+const fn count(bytes: &[u8]) -> usize {
+    count_regex!("b+", bytes)
+}
+
+const fn count_anchored(bytes: &[u8]) -> usize {
+    count_regex!("^b+", bytes)
+}
+
+const fn count_empty(bytes: &[u8]) -> usize {
+    count_regex!("a*", bytes)
+}
+
+#[test]
+fn counts_several_non_overlapping_matches() {
+    assert_eq!(count(b"abbbcbd"), 2);
+}
+
+#[test]
+fn zero_matches_is_zero() {
+    assert_eq!(count(b"xyz"), 0);
+}
+
+#[test]
+fn anchored_pattern_counts_at_most_once() {
+    assert_eq!(count_anchored(b"bbbxbbb"), 1);
+    assert_eq!(count_anchored(b"xbbb"), 0);
+}
+
+#[test]
+fn empty_matches_advance_the_cursor_by_one() {
+    // "a*" matches the empty string between every non-"a" byte, plus each run of "a"s, so the
+    // cursor must advance even when a match consumes zero bytes or this would loop forever.
+    assert_eq!(count_empty(b"bbb"), 4);
+    assert_eq!(count_empty(b""), 1);
+    // The empty match directly after "aa" (at the "b") doesn't count separately, matching
+    // `regex::Regex::new("a*").find_iter("aabaa")`, which also yields 2.
+    assert_eq!(count_empty(b"aabaa"), 2);
+}
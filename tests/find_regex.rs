@@ -0,0 +1,40 @@
+use const_regex::find_regex;
+
+const fn find(bytes: &[u8]) -> Option<(usize, usize)> {
+    find_regex!("b+", bytes)
+}
+
+const fn find_anchored(bytes: &[u8]) -> Option<(usize, usize)> {
+    find_regex!("^b+", bytes)
+}
+
+const fn find_empty(bytes: &[u8]) -> Option<(usize, usize)> {
+    find_regex!("a*", bytes)
+}
+
+#[test]
+fn finds_leftmost_of_several_matches() {
+    assert_eq!(find(b"xbbyzbbb"), Some((1, 3)));
+}
+
+#[test]
+fn finds_longest_match_at_leftmost_position() {
+    assert_eq!(find(b"abbbc"), Some((1, 4)));
+}
+
+#[test]
+fn no_match_returns_none() {
+    assert_eq!(find(b"xyz"), None);
+}
+
+#[test]
+fn anchored_only_matches_at_start() {
+    assert_eq!(find_anchored(b"bbbx"), Some((0, 3)));
+    assert_eq!(find_anchored(b"xbbb"), None);
+}
+
+#[test]
+fn pattern_matching_empty_string_matches_at_start() {
+    assert_eq!(find_empty(b"bbb"), Some((0, 0)));
+    assert_eq!(find_empty(b"aab"), Some((0, 2)));
+}
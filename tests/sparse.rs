@@ -0,0 +1,28 @@
+use const_regex::match_regex;
+
+const fn dense(bytes: &[u8]) -> bool {
+    match_regex!("[a-zA-Z_][a-zA-Z0-9_]*", bytes)
+}
+
+const fn sparse(bytes: &[u8]) -> bool {
+    match_regex!(sparse "[a-zA-Z_][a-zA-Z0-9_]*", bytes)
+}
+
+#[test]
+fn sparse_and_dense_agree_on_matches() {
+    assert_eq!(dense(b"ident_1"), sparse(b"ident_1"));
+    assert!(dense(b"ident_1"));
+}
+
+#[test]
+fn sparse_and_dense_agree_on_non_matches() {
+    // No letter or underscore anywhere in "123", so the unanchored pattern can't match at all.
+    assert_eq!(dense(b"123"), sparse(b"123"));
+    assert!(!dense(b"123"));
+}
+
+#[test]
+fn sparse_and_dense_agree_on_empty_input() {
+    assert_eq!(dense(b""), sparse(b""));
+    assert!(!dense(b""));
+}